@@ -0,0 +1,166 @@
+//! Persisted per-project defaults.
+//!
+//! A `.git-fad.toml`, searched from the repo root upward, sets defaults that are
+//! otherwise hardcoded in `main`: case matching, normalization, whether to
+//! include untracked files, a list of default exclude globs, and the tie-break
+//! policy used when two candidates score equally. CLI flags override whatever
+//! the file provides.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use nucleo_matcher::pattern::{CaseMatching, Normalization};
+
+/// How to break ties between candidates with equal aggregate score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TieBreak {
+    /// Prefer the shorter path (the original heuristic).
+    ShorterPath,
+    /// Prefer the lexically smaller path.
+    Lexical,
+    /// Prefer the most recently modified worktree file.
+    MostRecentlyModified,
+}
+
+/// Resolved defaults. `case`/`normalization` feed the fuzzy matcher directly so
+/// the rest of the program never has to know they came from a file.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub case: CaseMatching,
+    pub normalization: Normalization,
+    pub include_untracked: bool,
+    pub exclude: Vec<String>,
+    pub tie_break: TieBreak,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            case: CaseMatching::Ignore,
+            normalization: Normalization::Smart,
+            include_untracked: true,
+            exclude: Vec::new(),
+            tie_break: TieBreak::ShorterPath,
+        }
+    }
+}
+
+impl Config {
+    /// Load `.git-fad.toml` by walking up from `start` (the repo root) through
+    /// its ancestors. Returns the default config when no file is found.
+    pub fn load(start: &Path) -> Result<Config> {
+        let mut config = Config::default();
+        if let Some(path) = find_config(start) {
+            let text = std::fs::read_to_string(&path)
+                .with_context(|| format!("reading config {}", path.display()))?;
+            config.apply_toml(&text, &path)?;
+        }
+        Ok(config)
+    }
+
+    /// Parse the small flat subset of TOML we need: `key = "string"`,
+    /// `key = true|false`, and `key = ["a", "b"]`.
+    fn apply_toml(&mut self, text: &str, path: &Path) -> Result<()> {
+        for (lineno, raw) in text.lines().enumerate() {
+            let line = strip_comment(raw).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or_else(|| {
+                anyhow::anyhow!("{}:{}: expected `key = value`", path.display(), lineno + 1)
+            })?;
+            let key = key.trim();
+            let value = value.trim();
+
+            let loc = || format!("{}:{}: key `{}`", path.display(), lineno + 1, key);
+
+            match key {
+                "case" => {
+                    self.case = match parse_string(value).as_deref() {
+                        Some("ignore") => CaseMatching::Ignore,
+                        Some("respect") | Some("sensitive") => CaseMatching::Respect,
+                        Some("smart") => CaseMatching::Smart,
+                        _ => return Err(anyhow::anyhow!("{}: expected \"ignore\", \"respect\", or \"smart\"", loc())),
+                    };
+                }
+                "normalization" => {
+                    self.normalization = match parse_string(value).as_deref() {
+                        Some("smart") => Normalization::Smart,
+                        Some("never") | Some("off") => Normalization::Never,
+                        _ => return Err(anyhow::anyhow!("{}: expected \"smart\" or \"never\"", loc())),
+                    };
+                }
+                "include_untracked" => {
+                    self.include_untracked = parse_bool(value)
+                        .ok_or_else(|| anyhow::anyhow!("{}: expected true or false", loc()))?;
+                }
+                "exclude" => {
+                    self.exclude = parse_string_array(value)
+                        .ok_or_else(|| anyhow::anyhow!("{}: expected an array of strings", loc()))?;
+                }
+                "tie_break" => {
+                    self.tie_break = match parse_string(value).as_deref() {
+                        Some("shorter") | Some("shorter-path") => TieBreak::ShorterPath,
+                        Some("lexical") => TieBreak::Lexical,
+                        Some("mtime") | Some("most-recently-modified") => {
+                            TieBreak::MostRecentlyModified
+                        }
+                        _ => return Err(anyhow::anyhow!(
+                            "{}: expected \"shorter\", \"lexical\", or \"mtime\"",
+                            loc()
+                        )),
+                    };
+                }
+                _ => return Err(anyhow::anyhow!("{}: unknown key", loc())),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Search `dir` and each ancestor for a `.git-fad.toml`.
+fn find_config(dir: &Path) -> Option<PathBuf> {
+    for ancestor in dir.ancestors() {
+        let candidate = ancestor.join(".git-fad.toml");
+        if candidate.is_file() {
+            return Some(candidate);
+        }
+    }
+    None
+}
+
+fn strip_comment(line: &str) -> &str {
+    // Comments only start at an unquoted `#`; our values never embed one.
+    match line.find('#') {
+        Some(idx) => &line[..idx],
+        None => line,
+    }
+}
+
+fn parse_string(value: &str) -> Option<String> {
+    let v = value.trim();
+    let inner = v.strip_prefix('"')?.strip_suffix('"')?;
+    Some(inner.to_string())
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim() {
+        "true" => Some(true),
+        "false" => Some(false),
+        _ => None,
+    }
+}
+
+fn parse_string_array(value: &str) -> Option<Vec<String>> {
+    let v = value.trim();
+    let inner = v.strip_prefix('[')?.strip_suffix(']')?;
+    let mut out = Vec::new();
+    for part in inner.split(',') {
+        let part = part.trim();
+        if part.is_empty() {
+            continue;
+        }
+        out.push(parse_string(part)?);
+    }
+    Some(out)
+}
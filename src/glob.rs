@@ -0,0 +1,242 @@
+//! Glob/pathspec pre-filter for the candidate set.
+//!
+//! Patterns are sorted into tiers so the common cases never touch the regex
+//! engine: exact literal paths go into a `HashSet` for O(1) lookup, bare
+//! basename globs (`*.rs`, `Cargo.*`) have their literal prefix/suffix fed into
+//! an Aho-Corasick automaton keyed on the path's final component, and only
+//! genuinely complex patterns (`**`, character classes, mid-path wildcards)
+//! fall back to a compiled `RegexSet`. Each candidate is tested exact → basename
+//! → regex, short-circuiting on the first hit; negated (`!`) patterns are
+//! applied as a final exclusion pass.
+
+use std::collections::HashSet;
+
+use aho_corasick::AhoCorasick;
+use anyhow::{Context, Result};
+use regex::RegexSet;
+
+/// How a basename literal is anchored against the path's final component.
+#[derive(Clone, Copy)]
+enum Anchor {
+    Prefix,
+    Suffix,
+}
+
+/// The three matching tiers built from one set of (non-negated or negated)
+/// patterns.
+#[derive(Default)]
+struct Tiers {
+    exact: HashSet<String>,
+    basename: Option<AhoCorasick>,
+    basename_anchors: Vec<Anchor>,
+    complex: Option<RegexSet>,
+    /// Whether this tier set holds any pattern at all.
+    populated: bool,
+}
+
+impl Tiers {
+    fn build(patterns: &[String]) -> Result<Self> {
+        let mut exact = HashSet::new();
+        let mut basename_literals: Vec<String> = Vec::new();
+        let mut basename_anchors: Vec<Anchor> = Vec::new();
+        let mut complex_regexes: Vec<String> = Vec::new();
+
+        for pat in patterns {
+            match classify(pat) {
+                Kind::Exact(p) => {
+                    exact.insert(p);
+                }
+                Kind::Basename(literal, anchor) => {
+                    basename_literals.push(literal);
+                    basename_anchors.push(anchor);
+                }
+                Kind::Complex(body) => complex_regexes.push(glob_to_regex(&body)),
+            }
+        }
+
+        let basename = if basename_literals.is_empty() {
+            None
+        } else {
+            Some(
+                AhoCorasick::new(&basename_literals)
+                    .context("building Aho-Corasick automaton for basename globs")?,
+            )
+        };
+
+        let complex = if complex_regexes.is_empty() {
+            None
+        } else {
+            Some(RegexSet::new(&complex_regexes).context("compiling glob pattern regexes")?)
+        };
+
+        Ok(Tiers {
+            populated: !patterns.is_empty(),
+            exact,
+            basename,
+            basename_anchors,
+            complex,
+        })
+    }
+
+    fn is_match(&self, path: &str) -> bool {
+        if self.exact.contains(path) {
+            return true;
+        }
+
+        if let Some(ac) = &self.basename {
+            let base = basename(path);
+            // Overlapping search, so an unanchored hit cannot hide an anchored one.
+            for m in ac.find_overlapping_iter(base) {
+                let anchored = match self.basename_anchors[m.pattern().as_usize()] {
+                    Anchor::Prefix => m.start() == 0,
+                    Anchor::Suffix => m.end() == base.len(),
+                };
+                if anchored {
+                    return true;
+                }
+            }
+        }
+
+        if let Some(set) = &self.complex {
+            if set.is_match(path) {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+/// A compiled include/exclude filter over repo-relative path strings.
+pub struct PathFilter {
+    include: Tiers,
+    exclude: Tiers,
+}
+
+impl PathFilter {
+    /// Compile a list of glob/pathspec arguments. Patterns prefixed with `!`
+    /// become exclusions; the rest are inclusions.
+    pub fn new(patterns: &[String]) -> Result<Self> {
+        let mut includes = Vec::new();
+        let mut excludes = Vec::new();
+        for pat in patterns {
+            if let Some(rest) = pat.strip_prefix('!') {
+                excludes.push(rest.to_string());
+            } else {
+                includes.push(pat.clone());
+            }
+        }
+        Ok(PathFilter {
+            include: Tiers::build(&includes)?,
+            exclude: Tiers::build(&excludes)?,
+        })
+    }
+
+    /// A path is kept when it matches an include pattern (or no include patterns
+    /// were given) and is not caught by any exclude pattern.
+    pub fn matches(&self, path: &str) -> bool {
+        let included = !self.include.populated || self.include.is_match(path);
+        included && !self.exclude.is_match(path)
+    }
+}
+
+enum Kind {
+    Exact(String),
+    Basename(String, Anchor),
+    Complex(String),
+}
+
+/// Sort a single pattern into the cheapest tier that can represent it.
+fn classify(pat: &str) -> Kind {
+    let has_meta = pat.contains(['*', '?', '[', ']']);
+    if !has_meta {
+        return Kind::Exact(pat.to_string());
+    }
+
+    // A bare basename glob has no path separator, no recursive wildcard, and no
+    // character class or single-char wildcard — only a single `*` at one end.
+    if !pat.contains('/')
+        && !pat.contains("**")
+        && !pat.contains('?')
+        && !pat.contains('[')
+        && pat.matches('*').count() == 1
+    {
+        if let Some(suffix) = pat.strip_prefix('*') {
+            return Kind::Basename(suffix.to_string(), Anchor::Suffix);
+        }
+        if let Some(prefix) = pat.strip_suffix('*') {
+            return Kind::Basename(prefix.to_string(), Anchor::Prefix);
+        }
+    }
+
+    Kind::Complex(pat.to_string())
+}
+
+/// Translate a glob into an anchored regex for the complex tier.
+fn glob_to_regex(glob: &str) -> String {
+    let mut re = String::with_capacity(glob.len() * 2 + 2);
+    re.push('^');
+    let bytes = glob.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] as char {
+            '*' => {
+                if i + 1 < bytes.len() && bytes[i + 1] == b'*' {
+                    // `**/` matches zero or more leading/intermediate segments, so
+                    // a pattern like `src/**/*.rs` still matches `src/main.rs`.
+                    if i + 2 < bytes.len() && bytes[i + 2] == b'/' {
+                        re.push_str("(?:.*/)?");
+                        i += 2;
+                    } else {
+                        // A bare/trailing `**` crosses path separators.
+                        re.push_str(".*");
+                        i += 1;
+                    }
+                } else {
+                    re.push_str("[^/]*");
+                }
+            }
+            '?' => re.push_str("[^/]"),
+            // Character classes pass through verbatim.
+            '[' => re.push('['),
+            ']' => re.push(']'),
+            c if "\\.+()|{}^$".contains(c) => {
+                re.push('\\');
+                re.push(c);
+            }
+            c => re.push(c),
+        }
+        i += 1;
+    }
+    re.push('$');
+    re
+}
+
+/// Final path component, or the whole string when there is no separator.
+fn basename(path: &str) -> &str {
+    match path.rfind('/') {
+        Some(idx) => &path[idx + 1..],
+        None => path,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn double_star_include_matches_zero_or_more_segments() {
+        let filter = PathFilter::new(&["src/**/*.rs".to_string()]).unwrap();
+        assert!(filter.matches("src/main.rs"));
+        assert!(filter.matches("src/a/b.rs"));
+        assert!(!filter.matches("tests/x"));
+    }
+
+    #[test]
+    fn double_star_exclude_matches_zero_or_more_segments() {
+        let filter = PathFilter::new(&["!**/tests/**".to_string()]).unwrap();
+        assert!(!filter.matches("tests/x"));
+        assert!(!filter.matches("a/tests/x"));
+        assert!(filter.matches("src/main.rs"));
+    }
+}
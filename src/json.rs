@@ -0,0 +1,174 @@
+//! Structured `--json` output.
+//!
+//! Instead of staging, emit the full ranked candidate list as machine-readable
+//! records so editors and shell integrations can drive `git-fad` as a backend.
+//! Each record carries the repo-relative path, the [`FileMode`], the per-token
+//! and aggregate fuzzy scores, and the matched character index ranges within the
+//! path string. The matcher is asked for indices per token and they are unioned
+//! across tokens so a highlighter knows exactly which characters matched.
+
+use anyhow::Result;
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config as MatcherConfig, Matcher, Utf32Str};
+
+use crate::{FileEntry, FileMode};
+
+/// A candidate that matched every token, with its scores and highlight ranges.
+struct Record<'a> {
+    entry: &'a FileEntry,
+    display: String,
+    per_token: Vec<u32>,
+    aggregate: u32,
+    /// Inclusive `[start, end]` character ranges that matched.
+    ranges: Vec<(u32, u32)>,
+}
+
+/// Rank the candidates against the tokens and print the JSON array to stdout.
+pub fn emit(
+    candidates: &[FileEntry],
+    tokens: &[String],
+    case: CaseMatching,
+    normalization: Normalization,
+) -> Result<()> {
+    let mut matcher = Matcher::new(MatcherConfig::DEFAULT.match_paths());
+    let mut records: Vec<Record> = Vec::new();
+
+    for entry in candidates {
+        let display = entry.display().into_owned();
+        let mut char_buf = Vec::new();
+        let haystack = Utf32Str::new(&display, &mut char_buf);
+
+        let mut per_token = Vec::with_capacity(tokens.len());
+        let mut hit_chars: Vec<u32> = Vec::new();
+        let mut matched_all = true;
+
+        for tok in tokens {
+            let pattern = Pattern::parse(tok, case, normalization);
+            let mut idx = Vec::new();
+            match pattern.indices(haystack, &mut matcher, &mut idx) {
+                Some(score) => {
+                    per_token.push(score);
+                    hit_chars.extend_from_slice(&idx);
+                }
+                None => {
+                    matched_all = false;
+                    break;
+                }
+            }
+        }
+
+        if !matched_all {
+            continue;
+        }
+
+        let aggregate = per_token.iter().copied().sum();
+        let ranges = collapse_ranges(&mut hit_chars);
+
+        records.push(Record {
+            entry,
+            display,
+            per_token,
+            aggregate,
+            ranges,
+        });
+    }
+
+    // Rank by aggregate score desc, then shorter path, then lexically smaller
+    // path. The configured tie-break policy is not applied here.
+    records.sort_by(|a, b| {
+        b.aggregate
+            .cmp(&a.aggregate)
+            .then_with(|| a.display.len().cmp(&b.display.len()))
+            .then_with(|| a.display.cmp(&b.display))
+    });
+
+    print!("{}", render(&records, tokens));
+    Ok(())
+}
+
+/// Union character indices into sorted, deduplicated inclusive ranges.
+fn collapse_ranges(chars: &mut Vec<u32>) -> Vec<(u32, u32)> {
+    chars.sort_unstable();
+    chars.dedup();
+    let mut ranges: Vec<(u32, u32)> = Vec::new();
+    for &c in chars.iter() {
+        match ranges.last_mut() {
+            Some(last) if c == last.1 + 1 => last.1 = c,
+            _ => ranges.push((c, c)),
+        }
+    }
+    ranges
+}
+
+fn mode_name(mode: &FileMode) -> String {
+    match mode {
+        FileMode::Regular => "regular".to_string(),
+        FileMode::Executable => "executable".to_string(),
+        FileMode::Symlink => "symlink".to_string(),
+        FileMode::Submodule => "submodule".to_string(),
+        FileMode::Other(bits) => format!("other:{:o}", bits),
+    }
+}
+
+/// Hand-roll the JSON so we don't pull in a serialization dependency just for
+/// one output mode.
+fn render(records: &[Record], tokens: &[String]) -> String {
+    let mut out = String::from("[");
+    for (i, rec) in records.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        out.push_str("\n  {");
+        out.push_str(&format!("\"path\":{},", quote(&rec.display)));
+        out.push_str(&format!("\"mode\":{},", quote(&mode_name(&rec.entry.mode))));
+        out.push_str(&format!(
+            "\"conflicted\":{},",
+            rec.entry.conflict.is_conflicted()
+        ));
+        out.push_str("\"scores\":{");
+        out.push_str("\"per_token\":{");
+        for (t, tok) in tokens.iter().enumerate() {
+            if t > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("{}:{}", quote(tok), rec.per_token[t]));
+        }
+        out.push_str("},");
+        out.push_str(&format!("\"aggregate\":{}", rec.aggregate));
+        out.push_str("},");
+        out.push_str("\"match_ranges\":[");
+        for (r, (start, end)) in rec.ranges.iter().enumerate() {
+            if r > 0 {
+                out.push(',');
+            }
+            out.push_str(&format!("[{},{}]", start, end));
+        }
+        out.push_str("]}");
+    }
+    if records.is_empty() {
+        out.push(']');
+    } else {
+        out.push_str("\n]");
+    }
+    out.push('\n');
+    out
+}
+
+/// Minimal JSON string escaping for paths and tokens.
+fn quote(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -1,10 +1,18 @@
 use anyhow::anyhow;
 use anyhow::{Context, Result};
+use bstr::{BString, ByteSlice};
 use git2::{Repository, Status, StatusOptions};
 
 use std::env;
 use std::path::{Path, PathBuf};
 
+mod config;
+mod glob;
+mod json;
+mod tui;
+
+use config::{Config, TieBreak};
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum FileMode {
     Regular,
@@ -14,35 +22,96 @@ pub enum FileMode {
     Other(u32),
 }
 
+/// Which index stage slots a path occupies. A path with only stage 0 present is
+/// unconflicted; any of the higher slots (base = 1, ours = 2, theirs = 3) means
+/// the path is mid-merge and still needs resolving.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictStages {
+    pub base: bool,
+    pub ours: bool,
+    pub theirs: bool,
+}
+
+impl ConflictStages {
+    pub fn is_conflicted(&self) -> bool {
+        self.base || self.ours || self.theirs
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FileEntry {
+    /// Faithful path reconstructed from the raw bytes; used for staging so the
+    /// file added to the index is exactly the file that matched.
     pub path: PathBuf,
+    /// Raw repo-relative path bytes as git stores them — the source of truth,
+    /// preserved even when the bytes are not valid UTF-8.
+    pub raw: BString,
     pub mode: FileMode,
+    pub conflict: ConflictStages,
+}
+
+impl FileEntry {
+    /// Lossy display string used only for fuzzy scoring; never fed back into
+    /// staging.
+    pub fn display(&self) -> std::borrow::Cow<'_, str> {
+        self.raw.to_str_lossy()
+    }
+}
+
+/// Reconstruct a [`PathBuf`] from raw git path bytes without losing information.
+/// On Unix the bytes map straight onto the `OsStr`; elsewhere we fall back to a
+/// lossy conversion since the platform can't represent arbitrary bytes anyway.
+fn pathbuf_from_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
 }
 
-pub fn stage_paths_libgit2(repo_path: &Path, paths: &[PathBuf]) -> Result<()> {
+/// Make a repo-relative path from a candidate path, rejecting anything outside
+/// the repository.
+fn relativize(repo_path: &Path, p: &Path) -> Result<PathBuf> {
+    if p.is_absolute() {
+        p.strip_prefix(repo_path).map(PathBuf::from).map_err(|_| {
+            anyhow!(
+                "path {} is not inside repository {}",
+                p.display(),
+                repo_path.display()
+            )
+        })
+    } else {
+        Ok(p.to_path_buf())
+    }
+}
+
+/// Stage candidate entries, resolving conflicts as we go. A conflicted path with
+/// no worktree file is a delete/modify conflict: there is nothing to add at
+/// stage 0, so we resolve it the `git rm` way by removing the entry (and its
+/// higher stages) from the index. Everything else is added normally, which also
+/// collapses content conflicts to stage 0.
+fn stage_entries(repo_path: &Path, entries: &[&FileEntry]) -> Result<()> {
     let repo = Repository::open(repo_path)
         .with_context(|| format!("opening git repository at {}", repo_path.display()))?;
     let mut index = repo
         .index()
         .with_context(|| format!("reading index for repo {}", repo_path.display()))?;
 
-    for p in paths {
-        let rel = if p.is_absolute() {
-            p.strip_prefix(repo_path).map(PathBuf::from).map_err(|_| {
-                anyhow!(
-                    "path {} is not inside repository {}",
-                    p.display(),
-                    repo_path.display()
-                )
-            })?
+    for entry in entries {
+        let rel = relativize(repo_path, &entry.path)?;
+        if entry.conflict.is_conflicted() && !repo_path.join(&rel).exists() {
+            index
+                .remove_path(&rel)
+                .with_context(|| format!("removing deleted {} from index", rel.display()))?;
         } else {
-            p.clone()
-        };
-
-        index
-            .add_path(&rel)
-            .with_context(|| format!("adding {} to index", rel.display()))?;
+            index
+                .add_path(&rel)
+                .with_context(|| format!("adding {} to index", rel.display()))?;
+        }
     }
 
     index.write().context("writing index after staging paths")?;
@@ -50,16 +119,54 @@ pub fn stage_paths_libgit2(repo_path: &Path, paths: &[PathBuf]) -> Result<()> {
     Ok(())
 }
 
+/// Whether a worktree file still contains textual merge-conflict markers. Files
+/// that are missing or not valid UTF-8 are treated as marker-free.
+fn contains_conflict_markers(repo_path: &Path, rel: &Path) -> bool {
+    let full = repo_path.join(rel);
+    let Ok(contents) = std::fs::read_to_string(&full) else {
+        return false;
+    };
+    contents.lines().any(|line| {
+        line.starts_with("<<<<<<< ")
+            || line == "|||||||"
+            || line.starts_with("||||||| ")
+            || line == "======="
+            || line.starts_with(">>>>>>> ")
+    })
+}
+
+/// Refuse to stage a conflicted file that still contains conflict markers unless
+/// the caller passed `--force`. Staging a conflicted path otherwise resolves it:
+/// `add_path` records the merged worktree blob at stage 0 and clears the higher
+/// stages from the index.
+fn ensure_resolvable(repo_path: &Path, entries: &[&FileEntry], force: bool) -> Result<()> {
+    if force {
+        return Ok(());
+    }
+    for entry in entries {
+        if entry.conflict.is_conflicted() && contains_conflict_markers(repo_path, &entry.path) {
+            return Err(anyhow!(
+                "{} still contains conflict markers; resolve it or pass --force",
+                entry.path.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
 // Use the high-performance matcher crate
-use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::pattern::Pattern;
 use nucleo_matcher::{Config as MatcherConfig, Matcher};
 
-fn collect_unstaged_and_untracked(repo_path: &std::path::Path) -> Result<Vec<FileEntry>> {
+fn collect_unstaged_and_untracked(
+    repo_path: &std::path::Path,
+    include_untracked: bool,
+) -> Result<Vec<FileEntry>> {
     let repo = Repository::open(repo_path)
         .with_context(|| format!("opening git repository at {}", repo_path.display()))?;
 
     let mut opts = StatusOptions::new();
-    opts.include_untracked(true)
+    opts.include_untracked(include_untracked)
         .include_ignored(false)
         .renames_head_to_index(true)
         .renames_from_rewrites(true)
@@ -70,6 +177,11 @@ fn collect_unstaged_and_untracked(repo_path: &std::path::Path) -> Result<Vec<Fil
         .statuses(Some(&mut opts))
         .with_context(|| format!("collecting git statuses for {}", repo_path.display()))?;
 
+    // Derive per-path conflict state from the index stage slots up front so we
+    // can tag candidates without re-reading the index for each one.
+    let conflicts = conflict_stages_by_path(&repo)
+        .with_context(|| format!("reading index conflicts for {}", repo_path.display()))?;
+
     let mut entries = Vec::new();
 
     for entry in statuses.iter() {
@@ -80,37 +192,167 @@ fn collect_unstaged_and_untracked(repo_path: &std::path::Path) -> Result<Vec<Fil
                 | Status::WT_MODIFIED
                 | Status::WT_DELETED
                 | Status::WT_TYPECHANGE
-                | Status::WT_RENAMED,
+                | Status::WT_RENAMED
+                | Status::CONFLICTED,
         ) {
-            if let Some(p) = entry.path() {
-                entries.push(FileEntry {
-                    path: PathBuf::from(p),
-                    mode: FileMode::Regular,
-                });
-            }
+            // Use the raw bytes so paths git stores as non-UTF-8 survive intact.
+            let raw = BString::from(entry.path_bytes());
+            let conflict = conflicts.get(raw.as_bytes()).cloned().unwrap_or_default();
+            entries.push(FileEntry {
+                path: pathbuf_from_bytes(raw.as_bytes()),
+                raw,
+                mode: FileMode::Regular,
+                conflict,
+            });
         }
     }
 
     Ok(entries)
 }
 
+/// Walk the index conflict slots and record, per repo-relative path, which
+/// stages (base/ours/theirs) are present.
+fn conflict_stages_by_path(
+    repo: &Repository,
+) -> Result<std::collections::HashMap<Vec<u8>, ConflictStages>> {
+    use std::collections::HashMap;
+
+    let index = repo.index().context("reading index for conflict scan")?;
+    let mut map: HashMap<Vec<u8>, ConflictStages> = HashMap::new();
+
+    // Older indexes without conflicts return an empty iterator rather than erroring.
+    let Ok(conflicts) = index.conflicts() else {
+        return Ok(map);
+    };
+
+    for conflict in conflicts {
+        let conflict = conflict.context("reading index conflict entry")?;
+        // Each slot carries the same path; pick whichever one is present.
+        for (slot, stages) in [
+            (&conflict.ancestor, ConflictSlot::Base),
+            (&conflict.our, ConflictSlot::Ours),
+            (&conflict.their, ConflictSlot::Theirs),
+        ] {
+            if let Some(entry) = slot {
+                let st = map.entry(entry.path.clone()).or_default();
+                match stages {
+                    ConflictSlot::Base => st.base = true,
+                    ConflictSlot::Ours => st.ours = true,
+                    ConflictSlot::Theirs => st.theirs = true,
+                }
+            }
+        }
+    }
+
+    Ok(map)
+}
+
+enum ConflictSlot {
+    Base,
+    Ours,
+    Theirs,
+}
+
 fn main() -> Result<()> {
     let prog = env::args().next().unwrap_or_else(|| "git-fad".into());
-    // Collect all remaining CLI args as independent fuzzy tokens
-    let tokens: Vec<String> = env::args().skip(1).collect();
-    if tokens.is_empty() {
-        eprintln!("Usage: {} <query tokens...>", prog);
+    // Collect all remaining CLI args. Flags (`-i`/`--interactive`, `--force`,
+    // `--json`, `--glob <pattern>` and the config overrides) may appear anywhere;
+    // every other argument is an independent fuzzy token.
+    let mut interactive = false;
+    let mut force = false;
+    let mut json_output = false;
+    let mut globs: Vec<String> = Vec::new();
+    let mut tokens: Vec<String> = Vec::new();
+    // CLI overrides for config-file defaults; `None` means "leave the file value".
+    let mut case_override: Option<nucleo_matcher::pattern::CaseMatching> = None;
+    let mut normalization_override: Option<nucleo_matcher::pattern::Normalization> = None;
+    let mut untracked_override: Option<bool> = None;
+    let mut tie_break_override: Option<TieBreak> = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-i" | "--interactive" => interactive = true,
+            "--force" => force = true,
+            "--json" => json_output = true,
+            "--case-sensitive" => {
+                case_override = Some(nucleo_matcher::pattern::CaseMatching::Respect)
+            }
+            "--ignore-case" => case_override = Some(nucleo_matcher::pattern::CaseMatching::Ignore),
+            "--normalization" => {
+                normalization_override = Some(nucleo_matcher::pattern::Normalization::Smart)
+            }
+            "--no-normalization" => {
+                normalization_override = Some(nucleo_matcher::pattern::Normalization::Never)
+            }
+            "--untracked" => untracked_override = Some(true),
+            "--no-untracked" => untracked_override = Some(false),
+            "--tie-break" => {
+                let policy = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--tie-break requires a policy argument"))?;
+                tie_break_override = Some(match policy.as_str() {
+                    "shorter" | "shorter-path" => TieBreak::ShorterPath,
+                    "lexical" => TieBreak::Lexical,
+                    "mtime" | "most-recently-modified" => TieBreak::MostRecentlyModified,
+                    other => return Err(anyhow!("unknown --tie-break policy: {}", other)),
+                });
+            }
+            "--glob" => {
+                let pat = args
+                    .next()
+                    .ok_or_else(|| anyhow!("--glob requires a pattern argument"))?;
+                globs.push(pat);
+            }
+            _ => tokens.push(arg),
+        }
+    }
+
+    if tokens.is_empty() && !interactive && !json_output {
+        eprintln!("Usage: {} [-i] <query tokens...>", prog);
         eprintln!("Examples:");
         eprintln!("  {} cargo", prog);
         eprintln!("  {} packages book type spec", prog);
         eprintln!("  {} src main rs", prog);
+        eprintln!("  {} -i", prog);
         return Ok(());
     }
 
     let repo_path = std::env::current_dir()?;
 
+    // 0) resolve project defaults from .git-fad.toml, then let CLI flags win.
+    let mut cfg = Config::load(&repo_path)?;
+    if let Some(case) = case_override {
+        cfg.case = case;
+    }
+    if let Some(normalization) = normalization_override {
+        cfg.normalization = normalization;
+    }
+    if let Some(untracked) = untracked_override {
+        cfg.include_untracked = untracked;
+    }
+    if let Some(tb) = tie_break_override {
+        cfg.tie_break = tb;
+    }
+
     // 1) collect candidates: only unstaged and untracked files
-    let candidates = collect_unstaged_and_untracked(&repo_path)?;
+    let mut candidates = collect_unstaged_and_untracked(&repo_path, cfg.include_untracked)?;
+
+    // 1b) optionally constrain the candidate set with glob/pathspec filters
+    // before any fuzzy scoring runs. Config exclude globs are applied as
+    // negated patterns alongside any CLI `--glob` arguments.
+    let mut patterns: Vec<String> = cfg.exclude.iter().map(|g| format!("!{}", g)).collect();
+    patterns.extend(globs.iter().cloned());
+    if !patterns.is_empty() {
+        let filter = glob::PathFilter::new(&patterns)?;
+        candidates.retain(|c| filter.matches(&c.display()));
+    }
+
+    // JSON mode: emit the ranked candidate list with highlight ranges instead
+    // of staging anything. An empty set must still print a valid `[]`, so this
+    // runs before the human "nothing to stage" message.
+    if json_output {
+        return json::emit(&candidates, &tokens, cfg.case, cfg.normalization);
+    }
 
     if candidates.is_empty() {
         println!(
@@ -120,114 +362,109 @@ fn main() -> Result<()> {
         return Ok(());
     }
 
-    // 2) Prepare haystacks: we match on file path strings
-    // Keep owned Strings so references remain valid during matching
-    let mut hay: Vec<String> = Vec::with_capacity(candidates.len());
-    for c in &candidates {
-        hay.push(c.path.to_string_lossy().into_owned());
+    // Interactive picker: refine the candidate list live and stage a multi-select.
+    if interactive {
+        return tui::run(
+            &repo_path,
+            &candidates,
+            &tokens.join(" "),
+            force,
+            cfg.case,
+            cfg.normalization,
+        );
     }
-    let hay_refs: Vec<&str> = hay.iter().map(|s| s.as_str()).collect();
+
+    // 2) Prepare haystacks: lossy display strings used only for scoring. The
+    // raw bytes on each candidate remain the source of truth for staging.
+    let hay: Vec<String> = candidates.iter().map(|c| c.display().into_owned()).collect();
 
     // 3) Create a matcher with path-friendly config
     let mut matcher: Matcher = Matcher::new(MatcherConfig::DEFAULT.match_paths());
 
     // 4) Multi-token fuzzy matching:
     // We treat each CLI token as a required fuzzy pattern. A candidate must match ALL tokens.
-    // We sum (aggregate) the individual token scores, and finally break ties by preferring
-    // shorter paths (heuristic for "more specific").
-    //
-    // Algorithm:
-    //   cumulative = empty map
-    //   for each token:
-    //       run fuzzy over full haystack -> map_this
-    //       if first token: cumulative = map_this
-    //       else: cumulative = intersection(cumulative, map_this) with scores added
-    //   pick max score; tie -> shorter path; next tie -> lexical
-    use std::collections::HashMap;
-
-    let mut cumulative: HashMap<&str, u32> = HashMap::new();
-    let mut first = true;
+    // Scores are accumulated per candidate *index* (not by display string, which
+    // can collide for distinct non-UTF-8 paths), so the entry we finally stage is
+    // provably the one that matched. An index drops out the moment a token fails it.
+    let mut scores: Vec<Option<u32>> = vec![Some(0); candidates.len()];
 
     for tok in &tokens {
-        let pattern = Pattern::parse(tok, CaseMatching::Ignore, Normalization::Smart);
-        let token_matches = pattern.match_list(&hay_refs, &mut matcher);
-
-        if token_matches.is_empty() {
-            // Early exit: one token matched nothing => overall no result
-            println!("No matches (token '{}' matched nothing)", tok);
-            return Ok(());
-        }
-
-        if first {
-            for (p, score) in token_matches {
-                cumulative.insert(p, score);
+        let pattern = Pattern::parse(tok, cfg.case, cfg.normalization);
+        let mut matched_any = false;
+        for (i, s) in hay.iter().enumerate() {
+            if scores[i].is_none() {
+                continue;
             }
-            first = false;
-        } else {
-            // Build lookup for this token
-            let mut this_map: HashMap<&str, u32> = HashMap::with_capacity(token_matches.len());
-            for (p, score) in token_matches {
-                this_map.insert(p, score);
-            }
-            // Retain only candidates also matched by this token; add their score
-            cumulative.retain(|p, total_score| {
-                if let Some(s) = this_map.get(p) {
-                    *total_score += *s;
-                    true
-                } else {
-                    false
+            let mut char_buf = Vec::new();
+            let haystack = nucleo_matcher::Utf32Str::new(s, &mut char_buf);
+            match pattern.score(haystack, &mut matcher) {
+                Some(score) => {
+                    scores[i] = Some(scores[i].unwrap() + score);
+                    matched_any = true;
                 }
-            });
-            if cumulative.is_empty() {
-                println!("No matches after applying tokens: {}", tokens.join(" "));
-                return Ok(());
+                None => scores[i] = None,
             }
         }
+        if !matched_any {
+            println!("No matches (token '{}' matched nothing)", tok);
+            return Ok(());
+        }
     }
 
-    if cumulative.is_empty() {
-        println!("No matches for query tokens: {}", tokens.join(" "));
-        return Ok(());
-    }
+    // Select best: highest aggregate score first, then the configured tie-break.
+    let tie_break = cfg.tie_break;
+    let mtime = |i: usize| {
+        std::fs::metadata(repo_path.join(&candidates[i].path))
+            .and_then(|m| m.modified())
+            .ok()
+    };
+    let best = (0..candidates.len())
+        .filter(|&i| scores[i].is_some())
+        .max_by(|&a, &b| {
+            scores[a].cmp(&scores[b]).then_with(|| match tie_break {
+                // shorter path wins, lexical as a final tie-break
+                TieBreak::ShorterPath => {
+                    hay[b].len().cmp(&hay[a].len()).then_with(|| hay[a].cmp(&hay[b]))
+                }
+                // lexically smaller path wins
+                TieBreak::Lexical => hay[b].cmp(&hay[a]),
+                // most recently modified wins, shorter path as a final tie-break
+                TieBreak::MostRecentlyModified => mtime(a)
+                    .cmp(&mtime(b))
+                    .then_with(|| hay[b].len().cmp(&hay[a].len())),
+            })
+        });
+
+    let top_index = match best {
+        Some(i) => i,
+        None => {
+            println!("No matches for query tokens: {}", tokens.join(" "));
+            return Ok(());
+        }
+    };
+    let best_score = scores[top_index].expect("selected index has a score");
 
-    // Select best (score desc, then shorter path, then lexical)
-    let (best_path_str, best_score) = cumulative
-        .into_iter()
-        .max_by(|(pa, sa), (pb, sb)| {
-            // Order by:
-            // 1. Higher aggregate score
-            // 2. Shorter path
-            // 3. Lexicographical order
-            sa.cmp(sb)
-                .then_with(|| pb.len().cmp(&pa.len())) // shorter path wins
-                .then_with(|| pa.cmp(pb))
-        })
-        .expect("non-empty cumulative map just ensured");
+    let top_entry = &candidates[top_index];
 
     println!(
         "Best match: {} (aggregate_score={}, tokens={})",
-        best_path_str,
+        top_entry.display(),
         best_score,
         tokens.join("+")
     );
 
-    // 7) Convert the matched string back to a repository-relative PathBuf and stage it
-    // Build a small lookup map from hay path -> index so we can reliably find the matched index
-    // without running into reference-level comparison issues.
-    let mut index_map: std::collections::HashMap<&str, usize> =
-        std::collections::HashMap::with_capacity(hay.len());
-    for (i, s) in hay.iter().enumerate() {
-        // store &str from the owned `hay` Strings so the references remain valid
-        index_map.insert(s.as_str(), i);
+    if top_entry.conflict.is_conflicted() {
+        println!(
+            "(conflicted) staging {} resolves the merge",
+            top_entry.display()
+        );
     }
-    let top_index = *index_map
-        .get(best_path_str)
-        .expect("matched path must exist in haystack");
 
-    let top_entry = &candidates[top_index];
+    // Refuse to resolve a file that still has conflict markers unless forced.
+    ensure_resolvable(&repo_path, &[top_entry], force)?;
 
-    // Stage via our git module using libgit2 (this will add the path to the index)
-    stage_paths_libgit2(&repo_path, &[top_entry.path.clone()]).with_context(|| {
+    // Stage via libgit2, resolving conflicts (including delete/modify) as needed.
+    stage_entries(&repo_path, &[top_entry]).with_context(|| {
         format!(
             "staging {} in repo {}",
             top_entry.path.display(),
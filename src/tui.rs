@@ -0,0 +1,280 @@
+//! Interactive fuzzy picker.
+//!
+//! This mirrors the ergonomics of an editor file picker: the candidate list is
+//! refined live as the user types query tokens, results are kept sorted by
+//! aggregate fuzzy score (with a stable secondary sort by file name so rows
+//! don't jump around between keystrokes), and multiple paths can be selected
+//! before staging them all in a single `stage_entries` call.
+
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use crossterm::cursor;
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{execute, queue};
+use nucleo_matcher::pattern::{CaseMatching, Normalization, Pattern};
+use nucleo_matcher::{Config as MatcherConfig, Matcher};
+
+use crate::{ensure_resolvable, stage_entries, FileEntry};
+
+/// One row in the picker: the candidate it points at plus its current aggregate
+/// score under the active query.
+struct Row {
+    candidate: usize,
+    score: u32,
+}
+
+/// Live state of the picker session.
+struct Picker<'a> {
+    candidates: &'a [FileEntry],
+    /// Lossy display strings, one per candidate, reused across keystrokes.
+    hay: Vec<String>,
+    matcher: Matcher,
+    case: CaseMatching,
+    normalization: Normalization,
+    query: String,
+    /// Rows matching the current query, already sorted for display.
+    rows: Vec<Row>,
+    /// Index into `rows` of the highlighted row.
+    cursor: usize,
+    /// Candidate indices the user has toggled on.
+    selected: Vec<bool>,
+}
+
+impl<'a> Picker<'a> {
+    fn new(
+        candidates: &'a [FileEntry],
+        initial_query: &str,
+        case: CaseMatching,
+        normalization: Normalization,
+    ) -> Self {
+        let hay = candidates
+            .iter()
+            .map(|c| c.display().into_owned())
+            .collect();
+        let mut picker = Self {
+            candidates,
+            hay,
+            matcher: Matcher::new(MatcherConfig::DEFAULT.match_paths()),
+            case,
+            normalization,
+            query: initial_query.to_string(),
+            rows: Vec::new(),
+            cursor: 0,
+            selected: vec![false; candidates.len()],
+        };
+        picker.requery();
+        picker
+    }
+
+    /// Re-run the fuzzy pipeline over the whole candidate set. Each whitespace
+    /// token must match, scores are summed, and an empty query matches every
+    /// candidate with a zero score so the full list is browsable.
+    fn requery(&mut self) {
+        let tokens: Vec<&str> = self.query.split_whitespace().collect();
+
+        let mut scores: Vec<Option<u32>> = if tokens.is_empty() {
+            vec![Some(0); self.candidates.len()]
+        } else {
+            vec![None; self.candidates.len()]
+        };
+
+        for (t, tok) in tokens.iter().enumerate() {
+            let pattern = Pattern::parse(tok, self.case, self.normalization);
+            let mut hit = vec![false; self.candidates.len()];
+            for (i, s) in self.hay.iter().enumerate() {
+                let mut char_buf = Vec::new();
+                let haystack = nucleo_matcher::Utf32Str::new(s, &mut char_buf);
+                if let Some(score) = pattern.score(haystack, &mut self.matcher) {
+                    hit[i] = true;
+                    if t == 0 {
+                        scores[i] = Some(score);
+                    } else if let Some(acc) = scores[i] {
+                        scores[i] = Some(acc + score);
+                    }
+                }
+            }
+            // Drop any candidate this token failed to match.
+            for (i, kept) in hit.iter().enumerate() {
+                if !kept {
+                    scores[i] = None;
+                }
+            }
+        }
+
+        let mut rows: Vec<Row> = scores
+            .into_iter()
+            .enumerate()
+            .filter_map(|(i, s)| s.map(|score| Row { candidate: i, score }))
+            .collect();
+
+        // Sort by score descending, then by file name ascending for a stable
+        // view so rows don't shuffle between keystrokes.
+        rows.sort_by(|a, b| {
+            b.score
+                .cmp(&a.score)
+                .then_with(|| self.hay[a.candidate].cmp(&self.hay[b.candidate]))
+        });
+
+        self.rows = rows;
+        if self.cursor >= self.rows.len() {
+            self.cursor = self.rows.len().saturating_sub(1);
+        }
+    }
+
+    fn move_cursor(&mut self, delta: isize) {
+        if self.rows.is_empty() {
+            return;
+        }
+        let len = self.rows.len() as isize;
+        let next = (self.cursor as isize + delta).rem_euclid(len);
+        self.cursor = next as usize;
+    }
+
+    fn toggle_current(&mut self) {
+        if let Some(row) = self.rows.get(self.cursor) {
+            let c = row.candidate;
+            self.selected[c] = !self.selected[c];
+        }
+    }
+
+    /// Candidate indices the user toggled on; falls back to the highlighted row
+    /// when nothing was explicitly selected so a bare Enter still stages the
+    /// highlighted match.
+    fn chosen(&self) -> Vec<usize> {
+        let explicit: Vec<usize> = self
+            .selected
+            .iter()
+            .enumerate()
+            .filter(|(_, on)| **on)
+            .map(|(i, _)| i)
+            .collect();
+        if !explicit.is_empty() {
+            return explicit;
+        }
+        self.rows
+            .get(self.cursor)
+            .map(|row| vec![row.candidate])
+            .unwrap_or_default()
+    }
+
+    fn render<W: Write>(&self, out: &mut W) -> Result<()> {
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            terminal::Clear(ClearType::All)
+        )?;
+        writeln!(out, "git-fad  (type to filter, ↑/↓ move, space select, enter stage, esc quit)\r")?;
+        writeln!(out, "> {}\r", self.query)?;
+        writeln!(out, "{} match(es)\r", self.rows.len())?;
+
+        let (_, rows_avail) = terminal::size().unwrap_or((80, 24));
+        let body = rows_avail.saturating_sub(3) as usize;
+        for (i, row) in self.rows.iter().take(body).enumerate() {
+            let marker = if self.selected[row.candidate] { "●" } else { " " };
+            let pointer = if i == self.cursor { ">" } else { " " };
+            // Surface conflicted paths with a distinct marker.
+            let conflict = if self.candidates[row.candidate].conflict.is_conflicted() {
+                "!"
+            } else {
+                " "
+            };
+            writeln!(
+                out,
+                "{} {}{} {}\r",
+                pointer,
+                marker,
+                conflict,
+                self.hay[row.candidate]
+            )?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+/// Run the interactive picker and stage whatever the user selects.
+pub fn run(
+    repo_path: &Path,
+    candidates: &[FileEntry],
+    initial_query: &str,
+    force: bool,
+    case: CaseMatching,
+    normalization: Normalization,
+) -> Result<()> {
+    let mut picker = Picker::new(candidates, initial_query, case, normalization);
+
+    terminal::enable_raw_mode().context("entering raw terminal mode")?;
+    let mut out = io::stderr();
+    execute!(out, terminal::EnterAlternateScreen, cursor::Hide)
+        .context("entering alternate screen")?;
+
+    let result = event_loop(&mut picker, &mut out);
+
+    execute!(out, cursor::Show, terminal::LeaveAlternateScreen).ok();
+    terminal::disable_raw_mode().ok();
+
+    let chosen = result?;
+    if chosen.is_empty() {
+        eprintln!("Nothing selected.");
+        return Ok(());
+    }
+
+    let entries: Vec<&FileEntry> = chosen.iter().map(|&i| &candidates[i]).collect();
+    // Refuse to resolve conflicted files that still have markers unless forced.
+    ensure_resolvable(repo_path, &entries, force)?;
+
+    stage_entries(repo_path, &entries)
+        .with_context(|| format!("staging {} selected path(s)", entries.len()))?;
+
+    for e in &entries {
+        if e.conflict.is_conflicted() {
+            println!("Staged {} (conflict resolved)", e.path.display());
+        } else {
+            println!("Staged {}", e.path.display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Drive the keystroke loop until the user stages or quits; returns the candidate
+/// indices to stage (empty on quit).
+fn event_loop<W: Write>(picker: &mut Picker, out: &mut W) -> Result<Vec<usize>> {
+    loop {
+        picker.render(out)?;
+
+        let Event::Key(KeyEvent {
+            code, modifiers, ..
+        }) = event::read().context("reading terminal event")?
+        else {
+            continue;
+        };
+
+        match (code, modifiers) {
+            (KeyCode::Esc, _) | (KeyCode::Char('c'), KeyModifiers::CONTROL) => {
+                return Ok(Vec::new());
+            }
+            (KeyCode::Enter, _) => {
+                return Ok(picker.chosen());
+            }
+            (KeyCode::Up, _) => picker.move_cursor(-1),
+            (KeyCode::Down, _) => picker.move_cursor(1),
+            (KeyCode::Char(' '), _) | (KeyCode::Tab, _) => {
+                picker.toggle_current();
+                picker.move_cursor(1);
+            }
+            (KeyCode::Backspace, _) => {
+                picker.query.pop();
+                picker.requery();
+            }
+            (KeyCode::Char(c), _) => {
+                picker.query.push(c);
+                picker.requery();
+            }
+            _ => {}
+        }
+    }
+}